@@ -1,19 +1,64 @@
 use std::{
+    collections::{hash_map::RandomState, HashMap},
     env,
-    fs::File,
+    fs::{self, File},
+    hash::{BuildHasher, Hasher},
     io::{self, BufRead, Write},
+    panic::{self, AssertUnwindSafe},
     path::PathBuf,
     sync::{
-        mpsc::{channel},
-        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{channel, Receiver},
+        Arc, Condvar, Mutex,
     },
     thread,
-    time::{Duration, Instant, SystemTime},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Json, Router};
 use reqwest::blocking::Client;
 use serde_json::json;
 
+// Shared handle to the latest results, read by the embedded status server in
+// watch mode and written by the worker pool.
+type SharedResults = Arc<Mutex<HashMap<String, WebsiteStatus>>>;
+
+// Where a worker stores a finished check. Watch mode keys by URL, since the
+// scheduler and status server only ever care about the latest check per URL.
+// The one-shot path keeps every result in arrival order instead, so a URL
+// that appears more than once in the input still gets one output row per
+// occurrence rather than losing all but one to a map overwrite.
+#[derive(Clone)]
+enum ResultsSink {
+    Keyed(SharedResults),
+    Ordered(Arc<Mutex<Vec<WebsiteStatus>>>),
+}
+
+impl ResultsSink {
+    fn store(&self, status: WebsiteStatus) {
+        match self {
+            ResultsSink::Keyed(results) => {
+                let mut results = results.lock().unwrap();
+                results.insert(status.url.clone(), status);
+            }
+            ResultsSink::Ordered(results) => {
+                let mut results = results.lock().unwrap();
+                results.push(status);
+            }
+        }
+    }
+}
+
+// Collapses a run's results to the most recent check per URL, used where
+// transition detection needs to compare against the previous run by URL even
+// though the underlying results may contain more than one row per URL.
+fn latest_per_url(results: &[WebsiteStatus]) -> HashMap<String, WebsiteStatus> {
+    results
+        .iter()
+        .map(|status| (status.url.clone(), status.clone()))
+        .collect()
+}
+
 // WebsiteStatus structure
 #[derive(Debug, Clone)]
 struct WebsiteStatus {
@@ -21,6 +66,10 @@ struct WebsiteStatus {
     action_status: Result<u16, String>,
     response_time: Duration,
     timestamp: SystemTime,
+    attempts: u32,
+    final_url: String,
+    expect_text_matched: Option<bool>,
+    healthy: bool,
 }
 
 // Config for command line args
@@ -30,6 +79,17 @@ struct Config {
     workers: usize,
     timeout: u64,
     retries: u32,
+    base_backoff_ms: u64,
+    max_backoff_ms: u64,
+    watch: bool,
+    interval_sec: u64,
+    output_path: PathBuf,
+    serve_addr: Option<String>,
+    events_path: Option<PathBuf>,
+    per_host_limit: usize,
+    max_redirects: usize,
+    expect_status: Option<Vec<u16>>,
+    expect_text: Option<String>,
 }
 
 fn parse_arguments() -> Result<Config, String> {
@@ -40,6 +100,17 @@ fn parse_arguments() -> Result<Config, String> {
         workers: num_cpus::get(),
         timeout: 5,
         retries: 0,
+        base_backoff_ms: 100,
+        max_backoff_ms: 5000,
+        watch: false,
+        interval_sec: 60,
+        output_path: PathBuf::from("status.json"),
+        serve_addr: None,
+        events_path: None,
+        per_host_limit: 4,
+        max_redirects: 10,
+        expect_status: None,
+        expect_text: None,
     };
 
     while let Some(arg) = args.next() {
@@ -87,6 +158,117 @@ fn parse_arguments() -> Result<Config, String> {
                     return Err("Error: Missing value for --retries argument.".to_string());
                 }
             }
+            "--base-backoff" => {
+                if let Some(ms_str) = args.next() {
+                    if let Ok(ms) = ms_str.parse::<u64>() {
+                        config.base_backoff_ms = ms;
+                    } else {
+                        return Err("Error: Invalid value for --base-backoff. Must be a non-negative integer.".to_string());
+                    }
+                } else {
+                    return Err("Error: Missing value for --base-backoff argument.".to_string());
+                }
+            }
+            "--max-backoff" => {
+                if let Some(ms_str) = args.next() {
+                    if let Ok(ms) = ms_str.parse::<u64>() {
+                        config.max_backoff_ms = ms;
+                    } else {
+                        return Err("Error: Invalid value for --max-backoff. Must be a non-negative integer.".to_string());
+                    }
+                } else {
+                    return Err("Error: Missing value for --max-backoff argument.".to_string());
+                }
+            }
+            "--output" => {
+                if let Some(path_str) = args.next() {
+                    config.output_path = PathBuf::from(path_str);
+                } else {
+                    return Err("Error: Missing path for --output argument.".to_string());
+                }
+            }
+            "--events" => {
+                if let Some(path_str) = args.next() {
+                    config.events_path = Some(PathBuf::from(path_str));
+                } else {
+                    return Err("Error: Missing path for --events argument.".to_string());
+                }
+            }
+            "--per-host-limit" => {
+                if let Some(limit_str) = args.next() {
+                    if let Ok(n) = limit_str.parse::<usize>() {
+                        if n == 0 {
+                            return Err("Error: --per-host-limit value must be greater than 0".to_string());
+                        }
+                        config.per_host_limit = n;
+                    } else {
+                        return Err("Error: Invalid value for --per-host-limit. Must be a positive integer.".to_string());
+                    }
+                } else {
+                    return Err("Error: Missing value for --per-host-limit argument.".to_string());
+                }
+            }
+            "--max-redirects" => {
+                if let Some(n_str) = args.next() {
+                    if let Ok(n) = n_str.parse::<usize>() {
+                        config.max_redirects = n;
+                    } else {
+                        return Err("Error: Invalid value for --max-redirects. Must be a non-negative integer.".to_string());
+                    }
+                } else {
+                    return Err("Error: Missing value for --max-redirects argument.".to_string());
+                }
+            }
+            "--expect-status" => {
+                if let Some(codes_str) = args.next() {
+                    let mut codes = Vec::new();
+                    for code_str in codes_str.split(',') {
+                        match code_str.trim().parse::<u16>() {
+                            Ok(code) => codes.push(code),
+                            Err(_) => {
+                                return Err(format!(
+                                    "Error: Invalid status code '{}' for --expect-status.",
+                                    code_str
+                                ));
+                            }
+                        }
+                    }
+                    config.expect_status = Some(codes);
+                } else {
+                    return Err("Error: Missing value for --expect-status argument.".to_string());
+                }
+            }
+            "--expect-text" => {
+                if let Some(text) = args.next() {
+                    config.expect_text = Some(text);
+                } else {
+                    return Err("Error: Missing value for --expect-text argument.".to_string());
+                }
+            }
+            "--serve" => {
+                if let Some(addr) = args.next() {
+                    config.serve_addr = Some(addr);
+                } else {
+                    return Err("Error: Missing address for --serve argument.".to_string());
+                }
+            }
+            "--watch" => {
+                config.watch = true;
+            }
+            "--interval" => {
+                if let Some(interval_str) = args.next() {
+                    if let Ok(s) = interval_str.parse::<u64>() {
+                        if s == 0 {
+                            return Err("Error: --interval value must be greater than 0".to_string());
+                        }
+                        config.interval_sec = s;
+                    } else {
+                        return Err("Error: Invalid value for --interval. Must be a positive integer.".to_string());
+                    }
+                } else {
+                    return Err("Error: Missing value for --interval argument.".to_string());
+                }
+            }
             _ if arg.starts_with("--") => {
                 return Err(format!("Error: Unknown argument: {}", arg));
             }
@@ -95,7 +277,12 @@ fn parse_arguments() -> Result<Config, String> {
     }
 
     if config.file_path.is_none() && config.urls.is_empty() {
-        eprintln!("Usage: website_checker [--file <path>] [suspicious link removed] [--workers N] [--timeout S] [--retries N]");
+        eprintln!(
+            "Usage: website_checker [--file <path>] [url ...] [--workers N] [--timeout S] [--retries N] \
+             [--base-backoff MS] [--max-backoff MS] [--per-host-limit N] [--max-redirects N] \
+             [--expect-status CODE,...] [--expect-text TEXT] [--output <path>] [--events <path>] \
+             [--watch --interval S] [--serve ADDR]"
+        );
         std::process::exit(2);
     }
 
@@ -116,76 +303,231 @@ fn read_urls_from_file(path: &PathBuf) -> Result<Vec<String>, io::Error> {
     Ok(urls)
 }
 
-fn check_website(url: String, timeout: Duration, retries: u32) -> WebsiteStatus {
-    let client = match Client::builder().timeout(timeout).build() {
+// Coarse, dependency-free source of randomness for jitter: good enough to
+// spread out retries, not meant to be cryptographically sound.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos());
+    (hasher.finish() as f64) / (u64::MAX as f64)
+}
+
+// Full jitter: sleep a random duration uniformly in [0, base * 2^attempt], capped at max_backoff.
+fn backoff_with_jitter(attempt: u32, base_backoff_ms: u64, max_backoff_ms: u64) -> Duration {
+    let exponential = base_backoff_ms.saturating_mul(2u64.saturating_pow(attempt));
+    let capped = exponential.min(max_backoff_ms);
+    let jittered_ms = (capped as f64 * random_fraction()) as u64;
+    Duration::from_millis(jittered_ms)
+}
+
+fn is_retryable_status(status_code: u16) -> bool {
+    status_code == 429 || (500..600).contains(&status_code)
+}
+
+// Bundles the knobs that shape a single check so they can be threaded through
+// the worker pool and watch scheduler as one value instead of eight.
+#[derive(Debug, Clone)]
+struct CheckSettings {
+    timeout: Duration,
+    retries: u32,
+    base_backoff_ms: u64,
+    max_backoff_ms: u64,
+    per_host_limit: usize,
+    max_redirects: usize,
+    expect_status: Option<Vec<u16>>,
+    expect_text: Option<String>,
+}
+
+// True if `status_code` should count as healthy, using `expect_status` when
+// configured and the usual 2xx/3xx range otherwise.
+fn is_healthy_status(status_code: u16, expect_status: &Option<Vec<u16>>) -> bool {
+    match expect_status {
+        Some(codes) => codes.contains(&status_code),
+        None => (200..400).contains(&status_code),
+    }
+}
+
+// A check is healthy only when the status code qualifies and, if an
+// --expect-text substring was configured, the body actually contained it.
+fn compute_healthy(
+    status: &Result<u16, String>,
+    expect_status: &Option<Vec<u16>>,
+    expect_text_matched: Option<bool>,
+) -> bool {
+    matches!(status, Ok(code) if is_healthy_status(*code, expect_status)) && expect_text_matched.unwrap_or(true)
+}
+
+// A counting semaphore built on Mutex + Condvar, since the worker pool here is
+// plain OS threads rather than a Tokio runtime (no tokio::sync::Semaphore).
+struct HostSemaphore {
+    available: Mutex<usize>,
+    condvar: Condvar,
+}
+
+impl HostSemaphore {
+    fn new(permits: usize) -> Self {
+        HostSemaphore {
+            available: Mutex::new(permits),
+            condvar: Condvar::new(),
+        }
+    }
+
+    fn release(&self) {
+        let mut available = self.available.lock().unwrap();
+        *available += 1;
+        self.condvar.notify_one();
+    }
+}
+
+// RAII guard: holding one counts against its host's limit, dropping it frees
+// the slot for the next request to that host.
+struct HostPermit {
+    semaphore: Arc<HostSemaphore>,
+}
+
+impl Drop for HostPermit {
+    fn drop(&mut self) {
+        self.semaphore.release();
+    }
+}
+
+// Registry of per-host semaphores, shared across the worker pool and created
+// lazily so hosts that never appear in the URL set don't cost anything.
+type HostLimiter = Arc<Mutex<HashMap<String, Arc<HostSemaphore>>>>;
+
+fn acquire_host_permit(limiter: &HostLimiter, host: &str, per_host_limit: usize) -> HostPermit {
+    let semaphore = {
+        let mut registry = limiter.lock().unwrap();
+        Arc::clone(
+            registry
+                .entry(host.to_string())
+                .or_insert_with(|| Arc::new(HostSemaphore::new(per_host_limit))),
+        )
+    };
+
+    {
+        let mut available = semaphore.available.lock().unwrap();
+        while *available == 0 {
+            available = semaphore.condvar.wait(available).unwrap();
+        }
+        *available -= 1;
+    }
+
+    HostPermit { semaphore }
+}
+
+fn extract_host(url: &str) -> Option<String> {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(|host| host.to_string()))
+}
+
+fn check_website(url: String, settings: &CheckSettings, host_limiter: &HostLimiter) -> WebsiteStatus {
+    let client = match Client::builder()
+        .timeout(settings.timeout)
+        .redirect(reqwest::redirect::Policy::limited(settings.max_redirects))
+        .build()
+    {
         Ok(client) => client,
         Err(e) => return WebsiteStatus {
-            url,
+            url: url.clone(),
             action_status: Err(format!("Failed to create HTTP client: {}", e)),
             response_time: Duration::from_secs(0),
             timestamp: SystemTime::now(),
+            attempts: 0,
+            final_url: url,
+            expect_text_matched: None,
+            healthy: false,
         },
     };
 
     let start_time = Instant::now();
     let mut last_result: Result<u16, String> = Err("Initial check not attempted".to_string());
+    let mut final_url = url.clone();
+    let mut expect_text_matched = None;
+    let mut attempts = 0;
+    let host = extract_host(&url);
+
+    for attempt in 0..=settings.retries {
+        attempts += 1;
+
+        let (result, retryable, attempt_final_url, attempt_text_matched) = {
+            // Hold the host's permit only for the in-flight request, not across
+            // the backoff sleep, so other attempts to the same host can proceed.
+            let _permit = host
+                .as_deref()
+                .map(|host| acquire_host_permit(host_limiter, host, settings.per_host_limit));
 
-    for attempt in 0..=retries {
-        last_result = match client.get(&url).send() {
-            Ok(response) => Ok(response.status().as_u16()),
-            Err(e) => Err(format!("Request error: {}", e)),
+            match client.get(&url).send() {
+                Ok(response) => {
+                    let status_code = response.status().as_u16();
+                    let response_final_url = response.url().to_string();
+                    let text_matched = settings.expect_text.as_ref().map(|expected| {
+                        response
+                            .text()
+                            .map(|body| body.contains(expected.as_str()))
+                            .unwrap_or(false)
+                    });
+                    let retryable = is_retryable_status(status_code);
+                    (Ok(status_code), retryable, response_final_url, text_matched)
+                }
+                Err(e) => {
+                    let retryable = e.is_timeout() || e.is_connect();
+                    (Err(format!("Request error: {}", e)), retryable, url.clone(), None)
+                }
+            }
         };
+        last_result = result;
+        final_url = attempt_final_url;
+        expect_text_matched = attempt_text_matched;
 
-        if last_result.is_ok() || attempt == retries {
+        if !retryable || attempt == settings.retries {
             break;
         }
 
-        thread::sleep(Duration::from_millis(100));
+        thread::sleep(backoff_with_jitter(
+            attempt,
+            settings.base_backoff_ms,
+            settings.max_backoff_ms,
+        ));
     }
 
     let response_time = start_time.elapsed();
     let timestamp = SystemTime::now();
+    let healthy = compute_healthy(&last_result, &settings.expect_status, expect_text_matched);
 
     WebsiteStatus {
         url,
         action_status: last_result,
         response_time,
         timestamp,
+        attempts,
+        final_url,
+        expect_text_matched,
+        healthy,
     }
 }
 
-fn main() -> Result<(), String> {
-    let config = parse_arguments()?;
-
-    let mut all_urls = config.urls;
-    if let Some(file_path) = &config.file_path {
-        match read_urls_from_file(file_path) {
-            Ok(urls_from_file) => all_urls.extend(urls_from_file),
-            Err(e) => eprintln!("Warning: Could not read URLs from file '{}': {}", file_path.display(), e),
-        }
-    }
-
-    if all_urls.is_empty() {
-        eprintln!("No URLs to check.");
-        return Ok(());
-    }
-
-    let num_workers = config.workers;
-    let timeout = Duration::from_secs(config.timeout);
-    let retries = config.retries;
-
-    // channels to communicate between threads
-    let (url_tx, url_rx) = channel::<String>();
-    let url_rx = Arc::new(Mutex::new(url_rx));
-    let results = Arc::new(Mutex::new(Vec::new()));
-
-    //worker threads
+// Spawns a pool of worker threads that pull URLs off `url_rx` until the channel
+// closes, check them, and store the outcome in `results` (keyed by URL for
+// `ResultsSink::Keyed`, appended in arrival order for `ResultsSink::Ordered`). When
+// `pending` is `Some`, it's decremented after each result is stored so callers
+// can tell when a batch of sent URLs has finished landing (used by watch mode
+// to detect a full sweep); `run_once` has no sweep to track and passes `None`.
+fn spawn_worker_pool(
+    num_workers: usize,
+    url_rx: Arc<Mutex<Receiver<String>>>,
+    results: ResultsSink,
+    pending: Option<Arc<AtomicUsize>>,
+    settings: CheckSettings,
+    host_limiter: HostLimiter,
+) -> Vec<thread::JoinHandle<()>> {
     let mut handles = Vec::new();
     for _ in 0..num_workers {
         let rx_clone = Arc::clone(&url_rx);
-        let results_clone = Arc::clone(&results);
-        let timeout_clone = timeout;
-        let retries_clone = retries;
+        let results_clone = results.clone();
+        let pending_clone = pending.clone();
+        let host_limiter_clone = Arc::clone(&host_limiter);
+        let settings_clone = settings.clone();
 
         let handle = thread::spawn(move || {
             loop {
@@ -199,27 +541,420 @@ fn main() -> Result<(), String> {
                     }
                 };
 
-                let status = check_website(url.clone(), timeout_clone, retries_clone);
-
-                // output result
-                println!(
-                    "{} - Status: {}, Response Time: {:?}, Timestamp: {:?}",
-                    status.url,
-                    match &status.action_status {
-                        Ok(code) => format!("{}", code),
-                        Err(err) => err.clone(),
-                    },
-                    status.response_time,
-                    status.timestamp
-                );
+                // Watch mode keeps these worker threads alive indefinitely, so a
+                // single panicking check must not take the thread down with it:
+                // that would leave `pending` stuck above zero and wedge the
+                // scheduler's sweep-completion wait forever. Catch it, log it,
+                // and keep pulling URLs instead.
+                let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                    check_website(url.clone(), &settings_clone, &host_limiter_clone)
+                }));
 
-                //store reusult
-                let mut res = results_clone.lock().unwrap();
-                res.push(status);
+                match outcome {
+                    Ok(status) => {
+                        println!(
+                            "{} - Status: {}, Response Time: {:?}, Timestamp: {:?}",
+                            status.url,
+                            match &status.action_status {
+                                Ok(code) => format!("{}", code),
+                                Err(err) => err.clone(),
+                            },
+                            status.response_time,
+                            status.timestamp
+                        );
+                        results_clone.store(status);
+                    }
+                    Err(_) => {
+                        eprintln!("Warning: Worker panicked while checking {}", url);
+                    }
+                }
+                if let Some(pending) = &pending_clone {
+                    pending.fetch_sub(1, Ordering::SeqCst);
+                }
             }
         });
         handles.push(handle);
     }
+    handles
+}
+
+fn status_json_fields(status: &Result<u16, String>) -> (serde_json::Value, serde_json::Value) {
+    match status {
+        Ok(code) => (json!(code), json!(null)),
+        Err(err) => (json!(null), json!(err)),
+    }
+}
+
+fn build_json_snapshot(results: &[WebsiteStatus]) -> Vec<serde_json::Value> {
+    results
+        .iter()
+        .map(|status| {
+            let (status_code, error_message) = status_json_fields(&status.action_status);
+
+            json!({
+                "url": status.url,
+                "status_code": status_code,
+                "response_time_ms": status.response_time.as_millis(),
+                "timestamp": format!("{:?}", status.timestamp),
+                "error": error_message,
+                "attempts": status.attempts,
+                "final_url": status.final_url,
+                "expect_text_matched": status.expect_text_matched,
+                "healthy": status.healthy
+            })
+        })
+        .collect()
+}
+
+// Writes the snapshot to a sibling `.tmp` file, fsyncs it, then renames it over
+// `output_path`. The rename is atomic on the same filesystem, so readers never
+// observe a truncated or partially-written file, even if we crash mid-write.
+fn write_results_json(results: &[WebsiteStatus], output_path: &PathBuf) -> Result<(), String> {
+    let json_array = build_json_snapshot(results);
+    let json_string = serde_json::to_string_pretty(&json_array)
+        .map_err(|e| format!("Error serializing to JSON: {}", e))?;
+
+    let tmp_path = output_path.with_extension("json.tmp");
+
+    let write_result = (|| -> Result<(), String> {
+        let mut file = File::create(&tmp_path)
+            .map_err(|e| format!("Error creating {}: {}", tmp_path.display(), e))?;
+        file.write_all(json_string.as_bytes())
+            .map_err(|e| format!("Error writing to {}: {}", tmp_path.display(), e))?;
+        file.sync_data()
+            .map_err(|e| format!("Error syncing {}: {}", tmp_path.display(), e))?;
+        fs::rename(&tmp_path, output_path).map_err(|e| {
+            format!(
+                "Error renaming {} to {}: {}",
+                tmp_path.display(),
+                output_path.display(),
+                e
+            )
+        })
+    })();
+
+    if write_result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+
+    write_result
+}
+
+fn unix_timestamp_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn escape_metric_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Renders the current results as Prometheus text-format metrics so the
+// checker can be scraped directly instead of requiring consumers to poll
+// status.json.
+fn render_prometheus_metrics(results: &[WebsiteStatus]) -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP website_up Whether the last check of the URL succeeded (1) or not (0).\n");
+    out.push_str("# TYPE website_up gauge\n");
+    for status in results {
+        let up = status.healthy as u8;
+        out.push_str(&format!(
+            "website_up{{url=\"{}\"}} {}\n",
+            escape_metric_label(&status.url),
+            up
+        ));
+    }
+
+    out.push_str("# HELP website_response_time_ms Response time of the last check, in milliseconds.\n");
+    out.push_str("# TYPE website_response_time_ms gauge\n");
+    for status in results {
+        out.push_str(&format!(
+            "website_response_time_ms{{url=\"{}\"}} {}\n",
+            escape_metric_label(&status.url),
+            status.response_time.as_millis()
+        ));
+    }
+
+    let last_check_secs = results
+        .iter()
+        .map(|status| status.timestamp)
+        .max()
+        .map(unix_timestamp_secs)
+        .unwrap_or(0);
+    out.push_str("# HELP website_last_check_timestamp Unix timestamp of the most recent completed check.\n");
+    out.push_str("# TYPE website_last_check_timestamp gauge\n");
+    out.push_str(&format!("website_last_check_timestamp {}\n", last_check_secs));
+
+    out
+}
+
+async fn status_json_handler(State(results): State<SharedResults>) -> Json<Vec<serde_json::Value>> {
+    let snapshot: Vec<WebsiteStatus> = results.lock().unwrap().values().cloned().collect();
+    Json(build_json_snapshot(&snapshot))
+}
+
+async fn metrics_handler(State(results): State<SharedResults>) -> impl IntoResponse {
+    let snapshot: Vec<WebsiteStatus> = results.lock().unwrap().values().cloned().collect();
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], render_prometheus_metrics(&snapshot))
+}
+
+// Runs the embedded status server on its own thread with a small dedicated
+// Tokio runtime, so the rest of the checker can stay on plain OS threads.
+fn spawn_status_server(addr: String, results: SharedResults) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                eprintln!("Warning: Failed to start status server runtime: {}", e);
+                return;
+            }
+        };
+
+        runtime.block_on(async move {
+            let app = Router::new()
+                .route("/status.json", get(status_json_handler))
+                .route("/metrics", get(metrics_handler))
+                .with_state(results);
+
+            let listener = match tokio::net::TcpListener::bind(&addr).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    eprintln!("Warning: Failed to bind status server on {}: {}", addr, e);
+                    return;
+                }
+            };
+
+            println!("Serving live status on http://{}", addr);
+
+            if let Err(e) = axum::serve(listener, app).await {
+                eprintln!("Warning: Status server stopped: {}", e);
+            }
+        });
+    })
+}
+
+// The output-side options shared by one-shot and watch runs: where to write
+// the status snapshot and, optionally, the state-transition event log.
+struct OutputConfig {
+    output_path: PathBuf,
+    events_path: Option<PathBuf>,
+}
+
+// A trimmed-down view of a previously recorded check, reconstructed from a
+// prior status.json so runs can be diffed across process restarts.
+#[derive(Debug, Clone)]
+struct PreviousStatus {
+    status: Result<u16, String>,
+    response_time_ms: u128,
+    healthy: bool,
+}
+
+fn to_previous_status(status: &WebsiteStatus) -> PreviousStatus {
+    PreviousStatus {
+        status: status.action_status.clone(),
+        response_time_ms: status.response_time.as_millis(),
+        healthy: status.healthy,
+    }
+}
+
+// Reads a prior status.json (if any) back into a lookup by URL so the first
+// sweep of a run can still detect transitions relative to the last run.
+fn load_previous_results(output_path: &PathBuf) -> HashMap<String, PreviousStatus> {
+    let contents = match fs::read_to_string(output_path) {
+        Ok(contents) => contents,
+        Err(_) => return HashMap::new(),
+    };
+
+    let parsed: serde_json::Value = match serde_json::from_str(&contents) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!(
+                "Warning: Could not parse previous {} for state comparison: {}",
+                output_path.display(),
+                e
+            );
+            return HashMap::new();
+        }
+    };
+
+    let mut previous = HashMap::new();
+    for entry in parsed.as_array().into_iter().flatten() {
+        let Some(url) = entry.get("url").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let status = match entry.get("status_code").and_then(|v| v.as_u64()) {
+            Some(code) => Ok(code as u16),
+            None => Err(entry
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown error")
+                .to_string()),
+        };
+        let response_time_ms = entry
+            .get("response_time_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as u128;
+        // Older status.json files predate the "healthy" field; fall back to the
+        // plain 2xx/3xx heuristic so comparisons against them still work.
+        let healthy = entry
+            .get("healthy")
+            .and_then(|v| v.as_bool())
+            .unwrap_or_else(|| matches!(status, Ok(code) if (200..400).contains(&code)));
+
+        previous.insert(url.to_string(), PreviousStatus { status, response_time_ms, healthy });
+    }
+    previous
+}
+
+#[derive(Debug)]
+enum Transition {
+    WentDown,
+    Recovered,
+    StatusCodeChanged { from: u16, to: u16 },
+    ResponseTimeRegressed { from_ms: u128, to_ms: u128 },
+}
+
+impl Transition {
+    fn description(&self) -> String {
+        match self {
+            Transition::WentDown => "went DOWN".to_string(),
+            Transition::Recovered => "RECOVERED".to_string(),
+            Transition::StatusCodeChanged { from, to } => {
+                format!("status code changed {} -> {}", from, to)
+            }
+            Transition::ResponseTimeRegressed { from_ms, to_ms } => {
+                format!("response time regressed {}ms -> {}ms", from_ms, to_ms)
+            }
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Transition::WentDown => "went_down",
+            Transition::Recovered => "recovered",
+            Transition::StatusCodeChanged { .. } => "status_code_changed",
+            Transition::ResponseTimeRegressed { .. } => "response_time_regressed",
+        }
+    }
+}
+
+// A regression only gets reported once it more than doubles the response time
+// and adds more than 200 milliseconds, so everyday jitter stays quiet.
+fn is_large_regression(old_ms: u128, new_ms: u128) -> bool {
+    new_ms > old_ms.saturating_mul(2) && new_ms.saturating_sub(old_ms) > 200
+}
+
+fn classify_transitions(old: &PreviousStatus, new: &WebsiteStatus) -> Vec<Transition> {
+    let mut transitions = Vec::new();
+
+    match (old.healthy, new.healthy) {
+        (true, false) => transitions.push(Transition::WentDown),
+        (false, true) => transitions.push(Transition::Recovered),
+        _ => {}
+    }
+
+    match (&old.status, &new.action_status) {
+        (Ok(old_code), Ok(new_code)) if old_code != new_code => {
+            transitions.push(Transition::StatusCodeChanged {
+                from: *old_code,
+                to: *new_code,
+            });
+        }
+        _ => {}
+    }
+
+    let new_ms = new.response_time.as_millis();
+    if is_large_regression(old.response_time_ms, new_ms) {
+        transitions.push(Transition::ResponseTimeRegressed {
+            from_ms: old.response_time_ms,
+            to_ms: new_ms,
+        });
+    }
+
+    transitions
+}
+
+fn transition_event(url: &str, transition: &Transition, old: &PreviousStatus, new: &WebsiteStatus) -> serde_json::Value {
+    let (old_status_code, old_error) = status_json_fields(&old.status);
+    let (new_status_code, new_error) = status_json_fields(&new.action_status);
+
+    json!({
+        "url": url,
+        "kind": transition.kind(),
+        "old_status_code": old_status_code,
+        "old_error": old_error,
+        "new_status_code": new_status_code,
+        "new_error": new_error,
+        "old_response_time_ms": old.response_time_ms,
+        "new_response_time_ms": new.response_time.as_millis(),
+        "timestamp": unix_timestamp_secs(new.timestamp),
+    })
+}
+
+fn append_event_lines(path: &PathBuf, lines: &[String]) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+    for line in lines {
+        writeln!(file, "{}", line)?;
+    }
+    Ok(())
+}
+
+// Diffs `current` against `previous`, logging every transition to stderr and,
+// if configured, appending one JSON object per line to the events log.
+fn report_transitions(
+    previous: &HashMap<String, PreviousStatus>,
+    current: &HashMap<String, WebsiteStatus>,
+    events_path: &Option<PathBuf>,
+) {
+    let mut event_lines = Vec::new();
+
+    for (url, new_status) in current {
+        let Some(old_status) = previous.get(url) else {
+            continue;
+        };
+
+        for transition in classify_transitions(old_status, new_status) {
+            eprintln!("[event] {}: {}", url, transition.description());
+            event_lines.push(transition_event(url, &transition, old_status, new_status).to_string());
+        }
+    }
+
+    if event_lines.is_empty() {
+        return;
+    }
+    if let Some(path) = events_path {
+        if let Err(e) = append_event_lines(path, &event_lines) {
+            eprintln!("Warning: Failed to write events to {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn run_once(
+    all_urls: Vec<String>,
+    num_workers: usize,
+    settings: CheckSettings,
+    outputs: &OutputConfig,
+) -> Result<(), String> {
+    let previous_results = load_previous_results(&outputs.output_path);
+
+    // channels to communicate between threads
+    let (url_tx, url_rx) = channel::<String>();
+    let url_rx = Arc::new(Mutex::new(url_rx));
+    // One row per input occurrence, in completion order, so a URL repeated in
+    // the input (e.g. via --file plus positional args) isn't silently lost.
+    let results: Arc<Mutex<Vec<WebsiteStatus>>> = Arc::new(Mutex::new(Vec::new()));
+    let host_limiter: HostLimiter = Arc::new(Mutex::new(HashMap::new()));
+
+    // No sweep to track here: run_once joins the worker handles directly once
+    // the channel closes, so it has no use for a pending-count handoff.
+    let handles = spawn_worker_pool(
+        num_workers,
+        url_rx,
+        ResultsSink::Ordered(Arc::clone(&results)),
+        None,
+        settings,
+        host_limiter,
+    );
 
     for url in all_urls {
         if let Err(e) = url_tx.send(url) {
@@ -235,36 +970,353 @@ fn main() -> Result<(), String> {
         }
     }
 
-    // json file
     let final_results = results.lock().unwrap();
-    let json_array: Vec<_> = final_results.iter().map(|status| {
-        let status_code = match &status.action_status {
-            Ok(code) => json!(code),
-            Err(_) => json!(null),
-        };
-        let error_message = match &status.action_status {
-            Ok(_) => json!(null),
-            Err(err) => json!(err),
-        };
+    let latest = latest_per_url(&final_results);
+    report_transitions(&previous_results, &latest, &outputs.events_path);
+    write_results_json(&final_results, &outputs.output_path)?;
+
+    println!("Results saved to {}", outputs.output_path.display());
+
+    Ok(())
+}
+
+// Per-URL schedule for watch mode: each URL carries its own `next_update`
+// instant and interval, and is pushed onto the work channel once it's due,
+// so URLs don't all have to share a single fixed-rate tick.
+struct UrlSchedule {
+    url: String,
+    next_update: Instant,
+    interval: Duration,
+}
+
+fn is_down(status: &WebsiteStatus) -> bool {
+    !status.healthy
+}
+
+// Failing URLs are rechecked more often than their configured interval so
+// recovery is noticed quickly, down to a one second floor.
+fn shortened_interval(interval: Duration) -> Duration {
+    (interval / 4).max(Duration::from_secs(1))
+}
+
+fn run_watch_mode(
+    all_urls: Vec<String>,
+    num_workers: usize,
+    settings: CheckSettings,
+    interval: Duration,
+    outputs: &OutputConfig,
+    serve_addr: Option<String>,
+) -> Result<(), String> {
+    let mut previous_results = load_previous_results(&outputs.output_path);
+
+    let (url_tx, url_rx) = channel::<String>();
+    let url_rx = Arc::new(Mutex::new(url_rx));
+    let results: SharedResults = Arc::new(Mutex::new(HashMap::new()));
+    let pending = Arc::new(AtomicUsize::new(0));
+    let host_limiter: HostLimiter = Arc::new(Mutex::new(HashMap::new()));
+
+    // Workers stay alive across cycles; url_tx is never dropped while we watch.
+    let _handles = spawn_worker_pool(
+        num_workers,
+        url_rx,
+        ResultsSink::Keyed(Arc::clone(&results)),
+        Some(Arc::clone(&pending)),
+        settings,
+        host_limiter,
+    );
 
-        json!({
-            "url": status.url,
-            "status_code": status_code,
-            "response_time_ms": status.response_time.as_millis(),
-            "timestamp": format!("{:?}", status.timestamp),
-            "error": error_message
+    if let Some(addr) = serve_addr {
+        let _server_handle = spawn_status_server(addr, Arc::clone(&results));
+    }
+
+    let mut schedules: Vec<UrlSchedule> = all_urls
+        .into_iter()
+        .map(|url| UrlSchedule {
+            url,
+            next_update: Instant::now(),
+            interval,
         })
-    }).collect();
+        .collect();
 
-    let json_string = serde_json::to_string_pretty(&json_array)
-        .map_err(|e| format!("Error serializing to JSON: {}", e))?;
+    println!(
+        "Watching {} URL(s) every {:?} (Ctrl+C to stop)...",
+        schedules.len(),
+        interval
+    );
 
-    let mut file = File::create("status.json")
-        .map_err(|e| format!("Error creating status.json: {}", e))?;
-    file.write_all(json_string.as_bytes())
-        .map_err(|e| format!("Error writing to status.json: {}", e))?;
+    loop {
+        let now = Instant::now();
+        let due: Vec<usize> = schedules
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| now >= s.next_update)
+            .map(|(i, _)| i)
+            .collect();
 
-    println!("Results saved to status.json");
+        if due.is_empty() {
+            thread::sleep(Duration::from_millis(200));
+            continue;
+        }
 
-    Ok(())
+        pending.fetch_add(due.len(), Ordering::SeqCst);
+        for &i in &due {
+            if let Err(e) = url_tx.send(schedules[i].url.clone()) {
+                eprintln!("Warning: Failed to send URL to worker thread: {}", e);
+                pending.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        // Wait for this sweep's checks to land before rewriting status.json.
+        while pending.load(Ordering::SeqCst) > 0 {
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        {
+            let final_results = results.lock().unwrap();
+            for &i in &due {
+                let is_failing = final_results
+                    .get(&schedules[i].url)
+                    .map(is_down)
+                    .unwrap_or(false);
+                schedules[i].interval = if is_failing {
+                    shortened_interval(interval)
+                } else {
+                    interval
+                };
+                schedules[i].next_update = Instant::now() + schedules[i].interval;
+            }
+
+            report_transitions(&previous_results, &final_results, &outputs.events_path);
+            previous_results = final_results
+                .iter()
+                .map(|(url, status)| (url.clone(), to_previous_status(status)))
+                .collect();
+
+            let snapshot: Vec<WebsiteStatus> = final_results.values().cloned().collect();
+            if let Err(e) = write_results_json(&snapshot, &outputs.output_path) {
+                eprintln!(
+                    "Warning: Failed to write {}: {}",
+                    outputs.output_path.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+fn main() -> Result<(), String> {
+    let config = parse_arguments()?;
+
+    let mut all_urls = config.urls;
+    if let Some(file_path) = &config.file_path {
+        match read_urls_from_file(file_path) {
+            Ok(urls_from_file) => all_urls.extend(urls_from_file),
+            Err(e) => eprintln!("Warning: Could not read URLs from file '{}': {}", file_path.display(), e),
+        }
+    }
+
+    if all_urls.is_empty() {
+        eprintln!("No URLs to check.");
+        return Ok(());
+    }
+
+    let num_workers = config.workers;
+    let settings = CheckSettings {
+        timeout: Duration::from_secs(config.timeout),
+        retries: config.retries,
+        base_backoff_ms: config.base_backoff_ms,
+        max_backoff_ms: config.max_backoff_ms,
+        per_host_limit: config.per_host_limit,
+        max_redirects: config.max_redirects,
+        expect_status: config.expect_status,
+        expect_text: config.expect_text,
+    };
+    let outputs = OutputConfig {
+        output_path: config.output_path,
+        events_path: config.events_path,
+    };
+
+    if config.watch {
+        let interval = Duration::from_secs(config.interval_sec);
+        run_watch_mode(
+            all_urls,
+            num_workers,
+            settings,
+            interval,
+            &outputs,
+            config.serve_addr,
+        )
+    } else {
+        if config.serve_addr.is_some() {
+            eprintln!("Warning: --serve has no effect without --watch; ignoring it.");
+        }
+        run_once(all_urls, num_workers, settings, &outputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_with_jitter_never_exceeds_the_cap() {
+        for attempt in 0..10 {
+            let delay = backoff_with_jitter(attempt, 100, 5000);
+            assert!(delay <= Duration::from_millis(5000));
+        }
+    }
+
+    #[test]
+    fn backoff_with_jitter_caps_before_overflowing() {
+        // A large attempt count would overflow the exponential term if it
+        // weren't saturating; the jittered delay should still be capped at max_backoff_ms.
+        let delay = backoff_with_jitter(64, 100, 5000);
+        assert!(delay <= Duration::from_millis(5000));
+    }
+
+    #[test]
+    fn is_retryable_status_covers_429_and_5xx() {
+        assert!(is_retryable_status(429));
+        assert!(is_retryable_status(500));
+        assert!(is_retryable_status(503));
+        assert!(is_retryable_status(599));
+    }
+
+    #[test]
+    fn is_retryable_status_excludes_other_4xx_and_2xx() {
+        assert!(!is_retryable_status(200));
+        assert!(!is_retryable_status(301));
+        assert!(!is_retryable_status(400));
+        assert!(!is_retryable_status(404));
+    }
+
+    fn sample_status(status_code: Result<u16, String>, response_time_ms: u64, healthy: bool) -> WebsiteStatus {
+        WebsiteStatus {
+            url: "http://example.test".to_string(),
+            action_status: status_code,
+            response_time: Duration::from_millis(response_time_ms),
+            timestamp: SystemTime::now(),
+            attempts: 1,
+            final_url: "http://example.test".to_string(),
+            expect_text_matched: None,
+            healthy,
+        }
+    }
+
+    #[test]
+    fn is_large_regression_requires_doubling_and_a_200ms_floor() {
+        assert!(!is_large_regression(100, 250)); // doubles, but only +150ms
+        assert!(!is_large_regression(100, 150)); // neither
+        assert!(is_large_regression(50, 300)); // doubles and +250ms
+    }
+
+    #[test]
+    fn classify_transitions_detects_went_down() {
+        let old = PreviousStatus { status: Ok(200), response_time_ms: 50, healthy: true };
+        let new = sample_status(Ok(503), 50, false);
+
+        let transitions = classify_transitions(&old, &new);
+        assert!(transitions.iter().any(|t| matches!(t, Transition::WentDown)));
+    }
+
+    #[test]
+    fn classify_transitions_detects_recovery() {
+        let old = PreviousStatus { status: Err("Request error".to_string()), response_time_ms: 50, healthy: false };
+        let new = sample_status(Ok(200), 50, true);
+
+        let transitions = classify_transitions(&old, &new);
+        assert!(transitions.iter().any(|t| matches!(t, Transition::Recovered)));
+    }
+
+    #[test]
+    fn classify_transitions_detects_status_code_change_without_health_flip() {
+        let old = PreviousStatus { status: Ok(200), response_time_ms: 50, healthy: true };
+        let new = sample_status(Ok(201), 50, true);
+
+        let transitions = classify_transitions(&old, &new);
+        assert!(matches!(
+            transitions.as_slice(),
+            [Transition::StatusCodeChanged { from: 200, to: 201 }]
+        ));
+    }
+
+    #[test]
+    fn classify_transitions_detects_response_time_regression() {
+        let old = PreviousStatus { status: Ok(200), response_time_ms: 50, healthy: true };
+        let new = sample_status(Ok(200), 400, true);
+
+        let transitions = classify_transitions(&old, &new);
+        assert!(transitions
+            .iter()
+            .any(|t| matches!(t, Transition::ResponseTimeRegressed { from_ms: 50, to_ms: 400 })));
+    }
+
+    #[test]
+    fn classify_transitions_is_quiet_when_nothing_changed() {
+        let old = PreviousStatus { status: Ok(200), response_time_ms: 50, healthy: true };
+        let new = sample_status(Ok(200), 55, true);
+
+        assert!(classify_transitions(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn host_semaphore_release_gives_back_a_permit() {
+        let semaphore = HostSemaphore::new(1);
+        *semaphore.available.lock().unwrap() = 0;
+        semaphore.release();
+        assert_eq!(*semaphore.available.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn acquire_host_permit_reuses_the_same_semaphore_per_host() {
+        let limiter: HostLimiter = Arc::new(Mutex::new(HashMap::new()));
+
+        let permit = acquire_host_permit(&limiter, "example.test", 2);
+        assert_eq!(*permit.semaphore.available.lock().unwrap(), 1);
+        drop(permit);
+        assert_eq!(*limiter.lock().unwrap().get("example.test").unwrap().available.lock().unwrap(), 2);
+
+        // A second acquisition for the same host reuses the registered
+        // semaphore rather than creating a fresh one with a full count.
+        let registry_len_before = limiter.lock().unwrap().len();
+        let _permit = acquire_host_permit(&limiter, "example.test", 2);
+        assert_eq!(limiter.lock().unwrap().len(), registry_len_before);
+    }
+
+    #[test]
+    fn acquire_host_permit_keeps_hosts_independent() {
+        let limiter: HostLimiter = Arc::new(Mutex::new(HashMap::new()));
+
+        let _a = acquire_host_permit(&limiter, "a.test", 1);
+        let _b = acquire_host_permit(&limiter, "b.test", 1);
+
+        // Both hosts got their own semaphore, so both permits were granted
+        // even though each host's limit is 1.
+        assert_eq!(limiter.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn is_healthy_status_defaults_to_2xx_3xx() {
+        assert!(is_healthy_status(200, &None));
+        assert!(is_healthy_status(301, &None));
+        assert!(!is_healthy_status(404, &None));
+        assert!(!is_healthy_status(500, &None));
+    }
+
+    #[test]
+    fn is_healthy_status_honors_expect_status() {
+        let expect = Some(vec![200, 404]);
+        assert!(is_healthy_status(200, &expect));
+        assert!(is_healthy_status(404, &expect)); // explicitly allow-listed
+        assert!(!is_healthy_status(301, &expect)); // not in the list, even though it's a redirect
+    }
+
+    #[test]
+    fn compute_healthy_requires_both_status_and_body_match() {
+        assert!(compute_healthy(&Ok(200), &None, None));
+        assert!(compute_healthy(&Ok(200), &None, Some(true)));
+        assert!(!compute_healthy(&Ok(200), &None, Some(false))); // status ok, expect-text failed
+        assert!(!compute_healthy(&Ok(500), &None, Some(true))); // body matched, status unhealthy
+        assert!(!compute_healthy(&Err("Request error".to_string()), &None, None));
+    }
 }
\ No newline at end of file